@@ -1,4 +1,14 @@
+#[cfg(feature = "std")]
 use std::sync::{Arc, Mutex};
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+/// On bare metal there is no OS mutex; fall back to a spinlock. Its `lock()` is
+/// infallible and returns the guard directly, so `evaluate` picks the call by cfg.
+#[cfg(not(feature = "std"))]
+use spin::Mutex;
+
 use crate::belief::{AgentStrategy, SensorFeatures, PhysioState, Context};
 
 /// Versioned, resource-guarded container for cognitive agents.
@@ -35,6 +45,11 @@ impl AgentContainer {
 
     pub fn evaluate(&self, x: &SensorFeatures, phys: &PhysioState, ctx: &Context) -> f32 {
         // TODO: enforce resource limits and kill-switch
-        self.inner.lock().unwrap().eval(x, phys, ctx).confidence
+        #[cfg(feature = "std")]
+        let guard = self.inner.lock().unwrap();
+        // `spin::Mutex::lock` is infallible and returns the guard directly.
+        #[cfg(not(feature = "std"))]
+        let guard = self.inner.lock();
+        guard.eval(x, phys, ctx).confidence
     }
 }
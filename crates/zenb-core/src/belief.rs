@@ -0,0 +1,66 @@
+//! Belief inputs and per-agent evaluation.
+//!
+//! Cognitive agents ([`AgentStrategy`]) read the derived [`SensorFeatures`] and the
+//! [`PhysioState`]/[`Context`] snapshot for a tick and return a confidence-weighted
+//! target rate. The safety swarm weighs each agent's vote by that confidence rather
+//! than acting on raw samples.
+
+/// Derived sensor features for a single tick.
+#[derive(Debug, Clone)]
+pub struct SensorFeatures {
+    pub hr_bpm: Option<f32>,
+    pub rmssd: Option<f32>,
+    pub rr_bpm: Option<f32>,
+    /// Signal quality in `[0, 1]`; higher means more trustworthy.
+    pub quality: f32,
+    /// Motion artifact level; `0.0` is still.
+    pub motion: f32,
+}
+
+/// Physiological snapshot handed to an agent.
+#[derive(Debug, Clone)]
+pub struct PhysioState {
+    pub hr_bpm: Option<f32>,
+    pub rr_bpm: Option<f32>,
+    pub rmssd: Option<f32>,
+    /// Upstream confidence in the snapshot, in `[0, 1]`.
+    pub confidence: f32,
+}
+
+/// Runtime context: time of day, power state, and recent session count.
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub local_hour: u8,
+    pub is_charging: bool,
+    pub recent_sessions: u32,
+}
+
+/// An agent's proposal: a target rate and how confident it is in it.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentVerdict {
+    pub target_bpm: f32,
+    /// Confidence in `[0, 1]`, used as the agent's vote weight in the swarm.
+    pub confidence: f32,
+}
+
+/// A cognitive agent that proposes a target rate from the current belief inputs.
+#[derive(Debug, Clone)]
+pub struct AgentStrategy {
+    /// Fallback rate used when no measured respiration is available.
+    pub baseline_bpm: f32,
+}
+
+impl AgentStrategy {
+    pub fn new(baseline_bpm: f32) -> Self {
+        Self { baseline_bpm }
+    }
+
+    /// Evaluate the current inputs, returning a confidence-weighted target. The
+    /// confidence folds signal quality and the upstream snapshot confidence so a
+    /// noisy or motion-heavy frame carries less weight downstream.
+    pub fn eval(&self, x: &SensorFeatures, phys: &PhysioState, _ctx: &Context) -> AgentVerdict {
+        let confidence = (x.quality * phys.confidence / (1.0 + x.motion)).clamp(0.0, 1.0);
+        let target_bpm = x.rr_bpm.or(phys.rr_bpm).unwrap_or(self.baseline_bpm);
+        AgentVerdict { target_bpm, confidence }
+    }
+}
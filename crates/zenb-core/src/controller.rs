@@ -1,10 +1,26 @@
+use alloc::vec::Vec;
+
 use crate::belief::Context;
 use crate::estimator::Estimate;
 
+/// Number of recent estimates retained to gauge directional consistency before a
+/// large override is permitted.
+const ESTIMATE_HISTORY: usize = 8;
+
 #[derive(Debug, Clone)]
 pub struct ControllerConfig {
     pub decision_epsilon_bpm: f32,
     pub min_decision_interval_us: i64,
+    /// A deviation from the running decision larger than this is treated as a
+    /// *big jump* and gated behind the override guard below.
+    pub override_margin_bpm: f32,
+    /// Minimum estimate confidence required before a big jump may commit.
+    pub min_override_confidence: f32,
+    /// Minimum number of consecutive estimates pointing the same direction before
+    /// a big jump may commit (rejects single noisy samples).
+    pub min_consistent_estimates: usize,
+    /// Free-energy/urgency must exceed this for a big jump to commit.
+    pub free_energy_threshold: f32,
 }
 
 impl Default for ControllerConfig {
@@ -12,6 +28,10 @@ impl Default for ControllerConfig {
         Self {
             decision_epsilon_bpm: 0.1,
             min_decision_interval_us: 250_000,
+            override_margin_bpm: 1.0,
+            min_override_confidence: 0.6,
+            min_consistent_estimates: 3,
+            free_energy_threshold: 0.3,
         }
     }
 }
@@ -21,6 +41,8 @@ pub struct AdaptiveController {
     pub cfg: ControllerConfig,
     pub(crate) last_decision_ts_us: Option<i64>,
     pub(crate) last_decision_bpm: Option<f32>,
+    /// Ring buffer of recent raw rr estimates, newest last.
+    pub(crate) recent: Vec<f32>,
 }
 
 impl AdaptiveController {
@@ -29,27 +51,84 @@ impl AdaptiveController {
             cfg,
             last_decision_ts_us: None,
             last_decision_bpm: None,
+            recent: Vec::with_capacity(ESTIMATE_HISTORY),
         }
     }
 
-    /// Decide a target rate based on estimate and previous decision; returns (rate_bpm, changed)
-    pub fn decide(&mut self, est: &Estimate, ts_us: i64) -> (f32, bool) {
+    /// Decide a target rate based on estimate, urgency and previous decision;
+    /// returns `(rate_bpm, changed)`.
+    ///
+    /// Small corrections flip `changed` on the usual epsilon + minimum-interval
+    /// rule. A *large* deviation from the running decision only commits when all
+    /// override conditions hold: the confidence floor is met, `free_energy` is
+    /// above threshold, and a run of consecutive same-direction estimates confirms
+    /// the move. Otherwise the controller holds and the caller applies the gentle
+    /// slew from the `SafetyEnvelope`.
+    pub fn decide(&mut self, est: &Estimate, ts_us: i64, free_energy: f32) -> (f32, bool) {
         // If no RR estimate, fallback to last decision or default 6.0
         let base = est.rr_bpm.or(self.last_decision_bpm).unwrap_or(6.0);
         let target = base.clamp(4.0, 12.0);
-        let changed = match self.last_decision_bpm {
-            Some(prev) => (prev - target).abs() > self.cfg.decision_epsilon_bpm,
-            None => true,
-        } && match self.last_decision_ts_us {
+
+        if let Some(rr) = est.rr_bpm {
+            if self.recent.len() == ESTIMATE_HISTORY {
+                self.recent.remove(0);
+            }
+            self.recent.push(rr);
+        }
+
+        let interval_ok = match self.last_decision_ts_us {
             Some(last_ts) => (ts_us - last_ts) >= self.cfg.min_decision_interval_us,
             None => true,
         };
+        let deviation = match self.last_decision_bpm {
+            Some(prev) => crate::math::abs_f32(prev - target),
+            None => f32::INFINITY,
+        };
+
+        let changed = if deviation <= self.cfg.decision_epsilon_bpm {
+            false
+        } else if deviation > self.cfg.override_margin_bpm && self.last_decision_bpm.is_some() {
+            // Big jump: guarded by several independent safety conditions.
+            interval_ok
+                && est.confidence >= self.cfg.min_override_confidence
+                && free_energy > self.cfg.free_energy_threshold
+                && self.consistent_run(target)
+        } else {
+            interval_ok
+        };
+
         if changed {
             self.last_decision_bpm = Some(target);
             self.last_decision_ts_us = Some(ts_us);
         }
         (target, changed)
     }
+
+    /// True when the last `min_consistent_estimates` estimates all point toward
+    /// `target` from the previous decision (same sign of error).
+    fn consistent_run(&self, target: f32) -> bool {
+        let need = self.cfg.min_consistent_estimates;
+        let prev = match self.last_decision_bpm {
+            Some(p) => p,
+            None => return true,
+        };
+        if self.recent.len() < need {
+            return false;
+        }
+        let want = crate::math::signum_f32(target - prev);
+        // A sample equal to the previous decision carries no direction, so it must
+        // not count toward the run (otherwise `signum` maps a zero delta to `+1.0`
+        // and a flat sample would spuriously confirm an upward move).
+        let eps = self.cfg.decision_epsilon_bpm;
+        self.recent
+            .iter()
+            .rev()
+            .take(need)
+            .all(|&rr| {
+                let delta = rr - prev;
+                crate::math::abs_f32(delta) > eps && crate::math::signum_f32(delta) == want
+            })
+    }
 }
 
 /// Compute adaptive polling interval based on Free Energy (entropy) and confidence.
@@ -88,7 +167,7 @@ pub fn compute_poll_interval(
     }
 
     clamped_ms = clamped_ms.clamp(200.0, 30000.0);
-    clamped_ms.round() as u64
+    crate::math::round_f32(clamped_ms) as u64
 }
 
 #[cfg(test)]
@@ -100,9 +179,36 @@ mod tests {
     fn controller_basic_change() {
         let mut c = AdaptiveController::new(ControllerConfig::default());
         let mut e = Estimator::default();
-        let est = e.ingest(&[60.0, 30.0, 6.0], 0);
-        let (r, changed) = c.decide(&est, 0);
+        // Let the Kalman estimate converge to the measured 6 bpm before deciding.
+        let mut est = Estimate { rr_bpm: None, confidence: 0.0 };
+        for t in 0..20 {
+            est = e.ingest(&[60.0, 30.0, 6.0, 0.9, 0.0], t * 250_000);
+        }
+        let (r, changed) = c.decide(&est, 20 * 250_000, 0.0);
         assert!(changed);
-        assert!((r - 6.0).abs() < 1e-3);
+        assert!((r - 6.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn big_jump_needs_all_guards() {
+        let mut c = AdaptiveController::new(ControllerConfig::default());
+        // Establish a stable running decision at 6 bpm.
+        c.decide(&Estimate { rr_bpm: Some(6.0), confidence: 0.9 }, 0, 0.5);
+        // A single high-confidence jump to 11 bpm must not override yet: the
+        // consecutive-estimate guard has not accumulated.
+        let ts = c.cfg.min_decision_interval_us;
+        let (_, changed) = c.decide(&Estimate { rr_bpm: Some(11.0), confidence: 0.9 }, ts, 0.9);
+        assert!(!changed);
+        // Feed a consistent upward run; once confirmed, the jump commits.
+        let mut changed_final = false;
+        for i in 2..6 {
+            let t = ts * i;
+            let (_, ch) = c.decide(&Estimate { rr_bpm: Some(11.0), confidence: 0.9 }, t, 0.9);
+            // Accumulate: the override commits on the iteration where the run is
+            // first confirmed, after which the deviation is zero and later calls
+            // report `changed=false`.
+            changed_final |= ch;
+        }
+        assert!(changed_final);
     }
 }
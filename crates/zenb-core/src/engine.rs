@@ -0,0 +1,180 @@
+//! Engine-level privacy layer: timing-decoy patches.
+//!
+//! The replay/event stream records exact `ts_us` and rate patches, so an observer
+//! of the log could reconstruct a user's breathing and HRV rhythm. Inspired by
+//! cover-traffic padding, the [`DecoyScheduler`] interleaves *no-op decoy patches*
+//! into the emitted stream at randomized — but deterministically seeded — intervals
+//! so patch timing no longer reveals the true control cadence.
+//!
+//! Decoys are tagged ([`Patch::is_decoy`]) and carry the last real rate, so a naive
+//! log reader sees the same bytes as a real patch; emitting them never changes the
+//! scheduler's last real rate. Neither the controller nor
+//! [`SafetyEnvelope`](crate::safety::SafetyEnvelope) takes a [`Patch`], so neither
+//! checks the tag itself. [`DecoyScheduler::fold_into_hash`] folds the decoy RNG and
+//! schedule into a replay state hash, and two schedulers with the same seed produce
+//! identical decoy timing.
+
+/// Target inter-patch distribution for cover traffic.
+#[derive(Debug, Clone, Copy)]
+pub enum DecoyPadding {
+    /// Pad so that a patch (real or decoy) is emitted at least every `min_cadence_us`.
+    FixedRate { min_cadence_us: i64 },
+    /// Poisson-like cover traffic: gaps are exponentially distributed with the given
+    /// mean, jittered deterministically from the seed.
+    ExponentialJitter { mean_us: i64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct DecoyConfig {
+    pub enabled: bool,
+    pub padding: DecoyPadding,
+    /// Seed for the deterministic decoy RNG; replayed verbatim.
+    pub seed: u64,
+}
+
+impl Default for DecoyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            padding: DecoyPadding::FixedRate { min_cadence_us: 2_000_000 },
+            seed: 0x5EED_1234_5678_9ABC,
+        }
+    }
+}
+
+/// A rate patch, either a real control decision or a timing decoy. A decoy carries
+/// the last real rate so it is byte-indistinguishable from a real patch to a naive
+/// reader, but `is_decoy` lets the control path ignore it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Patch {
+    pub ts_us: i64,
+    pub rate_bpm: f32,
+    pub is_decoy: bool,
+}
+
+/// Deterministic `splitmix64` PRNG — no `std`, no global state, fully replayable.
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    fn next_unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Schedules decoy patches between real ones.
+#[derive(Debug, Clone)]
+pub struct DecoyScheduler {
+    pub cfg: DecoyConfig,
+    rng: SplitMix64,
+    last_rate_bpm: f32,
+    next_decoy_us: Option<i64>,
+}
+
+impl DecoyScheduler {
+    pub fn new(cfg: DecoyConfig) -> Self {
+        let rng = SplitMix64::new(cfg.seed);
+        Self { cfg, rng, last_rate_bpm: 6.0, next_decoy_us: None }
+    }
+
+    fn sample_gap(&mut self) -> i64 {
+        match self.cfg.padding {
+            DecoyPadding::FixedRate { min_cadence_us } => min_cadence_us.max(1),
+            DecoyPadding::ExponentialJitter { mean_us } => {
+                // Inverse-CDF sampling of an exponential gap.
+                let u = 1.0 - self.rng.next_unit(); // in (0, 1]
+                let gap = -crate::math::ln_f64(u) * mean_us.max(1) as f64;
+                (gap as i64).max(1)
+            }
+        }
+    }
+
+    /// Record a real patch so subsequent decoys mimic its rate and the schedule
+    /// is re-armed relative to it.
+    pub fn observe_real(&mut self, ts_us: i64, rate_bpm: f32) {
+        self.last_rate_bpm = rate_bpm;
+        if self.cfg.enabled {
+            let gap = self.sample_gap();
+            self.next_decoy_us = Some(ts_us + gap);
+        }
+    }
+
+    /// Emit any decoy patches that are due at or before `now_us`. Each returned
+    /// [`Patch`] has `is_decoy == true` and carries the last real rate.
+    pub fn poll(&mut self, now_us: i64) -> alloc::vec::Vec<Patch> {
+        let mut out = alloc::vec::Vec::new();
+        if !self.cfg.enabled {
+            return out;
+        }
+        while let Some(due) = self.next_decoy_us {
+            if due > now_us {
+                break;
+            }
+            out.push(Patch { ts_us: due, rate_bpm: self.last_rate_bpm, is_decoy: true });
+            let gap = self.sample_gap();
+            self.next_decoy_us = Some(due + gap);
+        }
+        out
+    }
+
+    /// Fold the decoy RNG and schedule into the deterministic replay state hash.
+    pub fn fold_into_hash(&self, h: &mut u64) {
+        *h = h.rotate_left(7) ^ self.rng.state.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        *h ^= self.next_decoy_us.map(|t| t as u64).unwrap_or(0).rotate_left(17);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_emits_no_decoys() {
+        let mut s = DecoyScheduler::new(DecoyConfig::default());
+        s.observe_real(0, 6.0);
+        assert!(s.poll(10_000_000).is_empty());
+    }
+
+    #[test]
+    fn fixed_rate_pads_and_is_tagged() {
+        let cfg = DecoyConfig {
+            enabled: true,
+            padding: DecoyPadding::FixedRate { min_cadence_us: 1_000_000 },
+            ..DecoyConfig::default()
+        };
+        let mut s = DecoyScheduler::new(cfg);
+        s.observe_real(0, 6.0);
+        let decoys = s.poll(2_500_000);
+        assert_eq!(decoys.len(), 2);
+        assert!(decoys.iter().all(|p| p.is_decoy && (p.rate_bpm - 6.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn same_seed_same_timing() {
+        let cfg = DecoyConfig {
+            enabled: true,
+            padding: DecoyPadding::ExponentialJitter { mean_us: 500_000 },
+            seed: 42,
+        };
+        let mut a = DecoyScheduler::new(cfg.clone());
+        let mut b = DecoyScheduler::new(cfg);
+        a.observe_real(0, 6.0);
+        b.observe_real(0, 6.0);
+        assert_eq!(a.poll(5_000_000), b.poll(5_000_000));
+    }
+}
@@ -0,0 +1,154 @@
+//! Respiration-rate estimator.
+//!
+//! A small linear Kalman filter over the respiration state `[rr_bpm, rr_rate]`
+//! with a constant-velocity transition. The posterior covariance is folded into
+//! a calibrated `confidence` so downstream `SafetyEnvelope` and
+//! `compute_poll_interval` get an uncertainty-aware signal rather than a bare
+//! point estimate.
+
+use nalgebra::{Matrix2, RowVector2, Vector2};
+
+/// A single respiration estimate handed to the controller.
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    /// Filtered respiration rate, or `None` before the first measurement.
+    pub rr_bpm: Option<f32>,
+    /// Calibrated confidence in `[0, 1]` derived from the posterior variance.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct EstimatorConfig {
+    /// Process-noise intensity (scaled by `dt` into `Q`).
+    pub process_noise: f32,
+    /// Baseline measurement-noise variance at perfect quality.
+    pub base_measurement_noise: f32,
+    /// Initial state covariance on the diagonal.
+    pub initial_variance: f32,
+    /// Physiological rate band (bpm) the filtered state is clamped to. This must
+    /// mirror [`SafetyConfig::rr_min`](crate::safety::SafetyConfig)/`rr_max` so the
+    /// Kalman clamp and the safety envelope stay in agreement; the defaults match
+    /// `SafetyConfig::default`.
+    pub rr_min: f32,
+    pub rr_max: f32,
+}
+
+impl Default for EstimatorConfig {
+    fn default() -> Self {
+        let safety = crate::safety::SafetyConfig::default();
+        Self {
+            process_noise: 0.05,
+            base_measurement_noise: 0.5,
+            initial_variance: 4.0,
+            rr_min: safety.rr_min,
+            rr_max: safety.rr_max,
+        }
+    }
+}
+
+/// Kalman estimator over `[rr_bpm, rr_rate_of_change]`.
+#[derive(Debug, Clone)]
+pub struct Estimator {
+    cfg: EstimatorConfig,
+    x: Vector2<f32>,
+    p: Matrix2<f32>,
+    last_ts_us: Option<i64>,
+    initialized: bool,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self::new(EstimatorConfig::default())
+    }
+}
+
+impl Estimator {
+    pub fn new(cfg: EstimatorConfig) -> Self {
+        let p = Matrix2::from_diagonal(&Vector2::new(cfg.initial_variance, cfg.initial_variance));
+        Self {
+            cfg,
+            x: Vector2::zeros(),
+            p,
+            last_ts_us: None,
+            initialized: false,
+        }
+    }
+
+    /// Ingest a feature frame `[hr_bpm, rmssd, rr_bpm, quality?, motion?]` stamped
+    /// at `ts_us`, run predict (and update when `rr_bpm` is present and finite), and
+    /// return the current [`Estimate`].
+    pub fn ingest(&mut self, features: &[f32], ts_us: i64) -> Estimate {
+        let dt = match self.last_ts_us {
+            Some(prev) => ((ts_us - prev).max(0) as f32) / 1_000_000.0,
+            None => 0.0,
+        };
+        self.last_ts_us = Some(ts_us);
+
+        // Predict: x = F·x, P = F·P·Fᵀ + Q.
+        let f = Matrix2::new(1.0, dt, 0.0, 1.0);
+        let q = self.cfg.process_noise
+            * Matrix2::new(dt * dt * dt / 3.0, dt * dt / 2.0, dt * dt / 2.0, dt);
+        self.x = f * self.x;
+        self.p = f * self.p * f.transpose() + q;
+
+        // Measurement update when a usable rr_bpm is present.
+        let measurement = features.get(2).copied().filter(|z| z.is_finite());
+        if let Some(z) = measurement {
+            let quality = features.get(3).copied().unwrap_or(1.0).clamp(0.0, 1.0);
+            let motion = features.get(4).copied().unwrap_or(0.0).max(0.0);
+            // Lower quality / higher motion => larger measurement noise.
+            let r = self.cfg.base_measurement_noise * (1.0 + motion) / (quality + 1e-3);
+
+            let h = RowVector2::new(1.0, 0.0);
+            let s = (h * self.p * h.transpose())[0] + r;
+            let innovation = z - (h * self.x)[0];
+            if s.is_finite() && crate::math::abs_f32(s) > f32::EPSILON && innovation.is_finite() {
+                let k = self.p * h.transpose() / s; // 2x1 gain
+                self.x += k * innovation;
+                let i = Matrix2::identity();
+                self.p = (i - k * h) * self.p;
+                self.initialized = true;
+            }
+        }
+
+        // Clamp the rate state to the physiological band (sourced from config so it
+        // cannot drift from the safety envelope's bounds).
+        self.x[0] = self.x[0].clamp(self.cfg.rr_min, self.cfg.rr_max);
+
+        let trace = self.p[(0, 0)] + self.p[(1, 1)];
+        let confidence = if trace.is_finite() {
+            (1.0 / (1.0 + trace.max(0.0))).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Estimate {
+            rr_bpm: if self.initialized { Some(self.x[0]) } else { None },
+            confidence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converges_to_measurement() {
+        let mut e = Estimator::default();
+        let mut est = Estimate { rr_bpm: None, confidence: 0.0 };
+        for t in 0..20 {
+            est = e.ingest(&[60.0, 30.0, 6.0, 0.9, 0.0], t * 250_000);
+        }
+        assert!((est.rr_bpm.unwrap() - 6.0).abs() < 0.5);
+        assert!(est.confidence > 0.3);
+    }
+
+    #[test]
+    fn predict_only_on_missing_measurement() {
+        let mut e = Estimator::default();
+        // NaN measurement is treated as missing: predict-only, no panic.
+        let est = e.ingest(&[60.0, 30.0, f32::NAN], 0);
+        assert!(est.rr_bpm.is_none());
+    }
+}
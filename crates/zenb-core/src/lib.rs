@@ -1,40 +1,31 @@
 //! ZenB core domain: deterministic domain types, replay, and state hashing.
+//!
+//! The deterministic core compiles on bare-metal targets under the
+//! `no_std + alloc` configuration. Build with `--no-default-features` to drop
+//! `std`; the default feature set keeps `std` enabled so hosted builds are
+//! unaffected. When `std` is off, transcendental math comes from `libm`,
+//! `AgentContainer` locks with a spinlock, and `serde_json` is not pulled in.
+//! Timestamps are monotonic `i64` microseconds in both builds.
+#![cfg_attr(not(feature = "std"), no_std)]
 
-pub mod domain;
-pub mod replay;
-pub mod policy;
-pub mod config;
+extern crate alloc;
+
+pub(crate) mod math;
+
+pub mod belief;
 pub mod estimator;
 pub mod safety;
 pub mod safety_swarm;
-pub mod trauma_cache;
+pub mod agent_container;
 pub mod controller;
-pub mod phase_machine;
-pub mod breath_engine;
-pub mod belief;
-pub mod resonance;
 pub mod engine;
-pub mod causal;
+pub mod validation;
 
-pub use domain::*;
-pub use replay::*;
-pub use policy::*;
-pub use config::*;
+pub use belief::*;
 pub use estimator::*;
 pub use safety::*;
 pub use safety_swarm::*;
-pub use trauma_cache::*;
+pub use agent_container::*;
 pub use controller::*;
-pub use phase_machine::*;
-pub use breath_engine::*;
-pub use belief::*;
-pub use resonance::*;
 pub use engine::*;
-pub use causal::*;
-
-#[cfg(test)]
-mod tests_determinism;
-#[cfg(test)]
-mod tests_estimator;
-#[cfg(test)]
-mod tests_config;
+pub use validation::*;
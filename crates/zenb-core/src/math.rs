@@ -0,0 +1,56 @@
+//! Float helpers that keep the deterministic core portable across the `std` and
+//! `no_std` builds. On hosted targets they forward to the inherent `f32`/`f64`
+//! methods; under `no_std` those methods are unavailable, so the equivalents come
+//! from `libm`. Call sites stay cfg-agnostic by routing through these functions.
+
+/// `|x|` for `f32`.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn abs_f32(x: f32) -> f32 {
+    x.abs()
+}
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn abs_f32(x: f32) -> f32 {
+    libm::fabsf(x)
+}
+
+/// Sign of `x` as `±1.0`, matching `f32::signum` including the `NaN -> NaN` and
+/// `±0.0 -> ±1.0` edge cases so std and no_std builds agree bit-for-bit (the replay
+/// hash depends on it).
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn signum_f32(x: f32) -> f32 {
+    x.signum()
+}
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn signum_f32(x: f32) -> f32 {
+    if x.is_nan() {
+        x
+    } else {
+        libm::copysignf(1.0, x)
+    }
+}
+
+/// Round `x` to the nearest integer, half away from zero (matching `f32::round`).
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn round_f32(x: f32) -> f32 {
+    x.round()
+}
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn round_f32(x: f32) -> f32 {
+    libm::roundf(x)
+}
+
+/// Natural logarithm of `x` for `f64`.
+///
+/// Always routed through `libm` — even on hosted builds — so that decoy timing and
+/// any other `ln`-derived state fold identically into the replay hash regardless of
+/// whether the crate was compiled with or without `std`.
+#[inline]
+pub(crate) fn ln_f64(x: f64) -> f64 {
+    libm::log(x)
+}
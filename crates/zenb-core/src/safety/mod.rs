@@ -1,10 +1,190 @@
-//! Safety Monitor module
+//! Safety envelope and LTL runtime verification.
 //!
-//! LTL runtime verification and Dharma-based ethical filtering
+//! [`SafetyEnvelope`] bounds how fast and how far the control rate may move, either
+//! binary-rejecting an out-of-bounds patch ([`SafetyEnvelope::allow_patch`]) or
+//! gradually slewing toward a target ([`SafetyEnvelope::slew_toward`]). The
+//! [`monitor`] submodule compiles LTL safety properties into monitor automata that
+//! run incrementally on every tick.
 
-pub mod dharma;
 pub mod monitor;
 
-pub use dharma::{AlignmentCategory, ComplexDecision, DharmaFilter};
 pub use monitor::{RuntimeState, SafetyMonitor, SafetyProperty, SafetyViolation, Severity};
 
+#[derive(Debug, Clone)]
+pub struct SafetyConfig {
+    pub rr_min: f32,
+    pub rr_max: f32,
+    pub max_rr_delta_per_min: f32,
+    pub max_hold_us: u64,
+    pub min_confidence: f32,
+    pub min_update_interval_us: u64,
+    /// Nominal slew rate (bpm/min) used for small errors, analogous to a clock
+    /// disciplining at a few PPM. A small fraction of `max_rr_delta_per_min`.
+    pub nominal_slew_bpm_per_min: f32,
+    /// Errors at or below this magnitude (bpm) are treated as *small* and slewed at
+    /// the nominal rate; larger errors escalate toward the max rate. This defines a
+    /// physiologically meaningful small-error band rather than tying it to the
+    /// minuscule `nominal_slew_bpm_per_min * max_slew_duration` product.
+    pub small_error_bpm: f32,
+    /// Window within which a slewing correction must complete; if the error is
+    /// too large to close even at the max slew rate, we fall back to a step jump.
+    pub max_slew_duration_us: u64,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            rr_min: 4.0, // breaths per minute
+            rr_max: 12.0,
+            max_rr_delta_per_min: 2.0, // bpm per minute
+            max_hold_us: 5 * 60_000_000, // 5 minutes
+            min_confidence: 0.3,
+            min_update_interval_us: 250_000, // 250ms
+            nominal_slew_bpm_per_min: 0.2, // ~10% of max delta, a gentle nudge
+            small_error_bpm: 0.5, // sub-bpm corrections stay gentle
+            max_slew_duration_us: 90_000_000, // ~90s to fully converge
+        }
+    }
+}
+
+/// Strategy chosen by [`SafetyEnvelope::slew_toward`], surfaced so `replay`/event
+/// logging can record *how* a rate transition was produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlewStrategy {
+    /// Single jump straight to the target, clamped to `[rr_min, rr_max]`.
+    Step,
+    /// Gentle correction at the nominal slew rate (small error).
+    SlewNominal,
+    /// Aggressive correction at the full `max_rr_delta_per_min` (large error).
+    SlewMax,
+}
+
+#[derive(Debug)]
+pub struct SafetyEnvelope {
+    pub cfg: SafetyConfig,
+    last_patch_ts_us: Option<i64>,
+    last_rate_bpm: Option<f32>,
+}
+
+impl SafetyEnvelope {
+    pub fn new(cfg: SafetyConfig) -> Self {
+        Self { cfg, last_patch_ts_us: None, last_rate_bpm: None }
+    }
+
+    /// Returns true if a proposed patch is allowed given confidence and rate limits.
+    pub fn allow_patch(&mut self, ts_us: i64, proposed_bpm: f32, confidence: f32) -> bool {
+        if confidence < self.cfg.min_confidence { return false; }
+        if proposed_bpm < self.cfg.rr_min || proposed_bpm > self.cfg.rr_max { return false; }
+        if let Some(last_ts) = self.last_patch_ts_us {
+            let elapsed_us = (ts_us - last_ts) as u64;
+            if elapsed_us < self.cfg.min_update_interval_us { return false; }
+            // rate change per minute constraint
+            if let Some(last_rate) = self.last_rate_bpm {
+                let delta = crate::math::abs_f32(proposed_bpm - last_rate);
+                // allowed delta scaled to elapsed
+                let allowed = self.cfg.max_rr_delta_per_min * (elapsed_us as f32 / 60_000_000f32);
+                if delta > allowed + f32::EPSILON { return false; }
+            }
+        }
+        true
+    }
+
+    pub fn record_patch(&mut self, ts_us: i64, new_rate: f32) {
+        self.last_patch_ts_us = Some(ts_us);
+        self.last_rate_bpm = Some(new_rate);
+    }
+
+    /// Close the error between `last_rate_bpm` and `target_bpm` gradually, the way
+    /// a clock disciplines toward a new offset, instead of binary-rejecting a large
+    /// delta. Returns a *bounded intermediate target* (clamped to `[rr_min, rr_max]`)
+    /// and the [`SlewStrategy`] that produced it.
+    ///
+    /// Small errors slew at `nominal_slew_bpm_per_min`; a large error escalates to the
+    /// full `max_rr_delta_per_min` when `confidence` is at least `min_confidence`. The
+    /// per-tick step is `rate * elapsed_us / 60e6`, capped at the remaining error so it
+    /// never overshoots. If the error cannot be closed within `max_slew_duration_us`
+    /// even at the max rate, we fall back to a single step jump to the clamped target.
+    /// The step is not otherwise tied to `max_slew_duration_us`: a correction slewed
+    /// at the nominal rate can take longer than that window to complete.
+    pub fn slew_toward(&self, ts_us: i64, target_bpm: f32, confidence: f32) -> (f32, SlewStrategy) {
+        let target = target_bpm.clamp(self.cfg.rr_min, self.cfg.rr_max);
+        let (last_rate, last_ts) = match (self.last_rate_bpm, self.last_patch_ts_us) {
+            (Some(r), Some(t)) => (r, t),
+            _ => return (target, SlewStrategy::Step),
+        };
+        let error = target - last_rate;
+        let abs_error = crate::math::abs_f32(error);
+        if abs_error <= f32::EPSILON {
+            return (target, SlewStrategy::SlewNominal);
+        }
+
+        let elapsed_us = (ts_us - last_ts).max(0) as f32;
+        // How much of the error the max rate can retire inside the full window.
+        let max_window_min = self.cfg.max_slew_duration_us as f32 / 60_000_000.0;
+        let reachable = self.cfg.max_rr_delta_per_min * max_window_min;
+        if abs_error > reachable {
+            // Too far to converge gently within the window: take the clamped jump.
+            return (target, SlewStrategy::Step);
+        }
+
+        // Small errors stay gentle. A large error escalates to the full max rate,
+        // but only when the estimate is trusted enough to act on aggressively; an
+        // uncertain large error is slewed at the nominal rate so a noisy sample
+        // cannot drive a fast correction. The strategy tag always matches the rate
+        // actually applied, so the event log stays truthful.
+        let conf = confidence.clamp(0.0, 1.0);
+        let escalate = abs_error > self.cfg.small_error_bpm && conf >= self.cfg.min_confidence;
+        let (rate_bpm_per_min, strategy) = if escalate {
+            (self.cfg.max_rr_delta_per_min, SlewStrategy::SlewMax)
+        } else {
+            (self.cfg.nominal_slew_bpm_per_min, SlewStrategy::SlewNominal)
+        };
+
+        let step = rate_bpm_per_min * (elapsed_us / 60_000_000.0);
+        // Never overshoot the target.
+        let applied = step.min(abs_error) * crate::math::signum_f32(error);
+        let intermediate = (last_rate + applied).clamp(self.cfg.rr_min, self.cfg.rr_max);
+        (intermediate, strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safety_freeze_on_low_confidence() {
+        let mut s = SafetyEnvelope::new(SafetyConfig::default());
+        assert!(!s.allow_patch(0, 6.0, 0.1));
+        assert!(s.allow_patch(0, 6.0, 0.9));
+    }
+
+    #[test]
+    fn rate_limit_delta() {
+        let mut s = SafetyEnvelope::new(SafetyConfig::default());
+        assert!(s.allow_patch(0, 6.0, 0.9));
+        s.record_patch(0, 6.0);
+        // quick change should be denied
+        assert!(!s.allow_patch(100_000, 9.0, 0.9));
+    }
+
+    #[test]
+    fn slew_closes_error_gently() {
+        let mut s = SafetyEnvelope::new(SafetyConfig::default());
+        s.record_patch(0, 6.0);
+        // A large-but-reachable correction slews instead of jumping.
+        let (bpm, strat) = s.slew_toward(30_000_000, 8.0, 0.9);
+        assert_eq!(strat, SlewStrategy::SlewMax);
+        assert!(bpm > 6.0 && bpm < 8.0);
+    }
+
+    #[test]
+    fn slew_steps_when_unreachable() {
+        let mut s = SafetyEnvelope::new(SafetyConfig::default());
+        s.record_patch(0, 4.0);
+        // 8 bpm error cannot close within the window at max rate -> step jump.
+        let (bpm, strat) = s.slew_toward(1_000_000, 12.0, 0.9);
+        assert_eq!(strat, SlewStrategy::Step);
+        assert!((bpm - 12.0).abs() < 1e-3);
+    }
+}
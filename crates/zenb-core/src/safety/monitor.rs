@@ -0,0 +1,351 @@
+//! LTL runtime verification.
+//!
+//! [`SafetyProperty`] formulas are *compiled once* into a [`SafetyMonitor`]
+//! automaton that is then evaluated incrementally on every control tick with no
+//! per-tick allocation, so it is cheap enough for the real-time loop. Safety
+//! properties (`always φ`) flag a [`SafetyViolation`] the instant `φ` is violated;
+//! bounded-liveness (`eventually within N ticks φ`) carries a countdown deadline
+//! and violates if it expires. The monitor state is part of the replay hash so
+//! violations are reproducible.
+
+use alloc::boxed::Box;
+
+/// The runtime fields atomic predicates may observe.
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeState {
+    pub ts_us: i64,
+    pub rate_bpm: f32,
+    pub confidence: f32,
+    pub rr_min: f32,
+    pub rr_max: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyViolation {
+    pub severity: Severity,
+}
+
+/// An atomic predicate over [`RuntimeState`] — a typed closure evaluated each tick.
+pub type Atom = Box<dyn Fn(&RuntimeState) -> bool + Send + Sync>;
+
+/// An LTL formula. Leaves are atomic predicates; nodes combine them with boolean
+/// and temporal operators.
+pub enum SafetyProperty {
+    Atom(Atom),
+    Not(Box<SafetyProperty>),
+    And(Box<SafetyProperty>, Box<SafetyProperty>),
+    Or(Box<SafetyProperty>, Box<SafetyProperty>),
+    /// `next φ`: φ must hold on the following tick.
+    ///
+    /// Evaluated online as *bounded lookahead by one tick*: the obligation raised at
+    /// tick `t` (that φ hold at `t+1`) is only discharged when tick `t+1` arrives, so
+    /// a `next φ` verdict is reported one tick late. This is the standard delay an
+    /// incremental monitor must accept — it cannot see the future within a single
+    /// tick. The consequence is that an obligation raised on the *final* observed
+    /// tick is never discharged (there is no following tick to evaluate it against).
+    Next(Box<SafetyProperty>),
+    /// `always φ`: φ must hold on every tick.
+    Always(Box<SafetyProperty>),
+    /// `eventually within N ticks φ`: φ must hold within `N` ticks of arming.
+    EventuallyWithin(u32, Box<SafetyProperty>),
+    /// `φ until ψ` (strong until): φ must hold until ψ becomes true, and ψ *must*
+    /// eventually hold. The safety part (φ failing before ψ) is flagged online by
+    /// `step`; the liveness part (ψ never holding) cannot be decided mid-trace and
+    /// is enforced at end of trace by [`SafetyMonitor::finish`].
+    Until(Box<SafetyProperty>, Box<SafetyProperty>),
+}
+
+/// Compiled, mutable automaton node mirroring a [`SafetyProperty`] subtree.
+enum Node {
+    Atom(Atom),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    /// `prev` records the child's value on the latest tick. `None` before the first
+    /// tick: no obligation is yet due, so the node reports vacuously `true`. From the
+    /// second tick on, the node reports the child's value on the current tick, the
+    /// verdict of the `next` obligation raised on the preceding tick (bounded
+    /// lookahead by one tick).
+    Next { child: Box<Node>, prev: Option<bool> },
+    Always { child: Box<Node>, violated: bool },
+    EventuallyWithin { child: Box<Node>, bound: u32, countdown: Option<u32>, violated: bool },
+    Until { lhs: Box<Node>, rhs: Box<Node>, violated: bool, satisfied: bool },
+}
+
+fn compile(p: SafetyProperty) -> Node {
+    match p {
+        SafetyProperty::Atom(a) => Node::Atom(a),
+        SafetyProperty::Not(c) => Node::Not(Box::new(compile(*c))),
+        SafetyProperty::And(a, b) => Node::And(Box::new(compile(*a)), Box::new(compile(*b))),
+        SafetyProperty::Or(a, b) => Node::Or(Box::new(compile(*a)), Box::new(compile(*b))),
+        SafetyProperty::Next(c) => Node::Next { child: Box::new(compile(*c)), prev: None },
+        SafetyProperty::Always(c) => Node::Always { child: Box::new(compile(*c)), violated: false },
+        SafetyProperty::EventuallyWithin(n, c) => Node::EventuallyWithin {
+            child: Box::new(compile(*c)),
+            bound: n,
+            countdown: Some(n),
+            violated: false,
+        },
+        SafetyProperty::Until(a, b) => Node::Until {
+            lhs: Box::new(compile(*a)),
+            rhs: Box::new(compile(*b)),
+            violated: false,
+            satisfied: false,
+        },
+    }
+}
+
+impl Node {
+    /// Advance this node by one tick, returning whether the subformula currently
+    /// holds. Temporal nodes latch their `violated` state as a side effect.
+    fn step(&mut self, s: &RuntimeState) -> bool {
+        match self {
+            Node::Atom(a) => a(s),
+            Node::Not(c) => !c.step(s),
+            Node::And(a, b) => {
+                // Step both so nested temporal state stays live.
+                let l = a.step(s);
+                let r = b.step(s);
+                l && r
+            }
+            Node::Or(a, b) => {
+                let l = a.step(s);
+                let r = b.step(s);
+                l || r
+            }
+            Node::Next { child, prev } => {
+                // Bounded lookahead by one tick: the obligation raised on the
+                // preceding tick comes due now and holds iff the child holds on
+                // this tick. Before the first tick no obligation is due, so the
+                // node holds vacuously.
+                let now = child.step(s);
+                let due = if prev.is_some() { now } else { true };
+                *prev = Some(now);
+                due
+            }
+            Node::Always { child, violated } => {
+                if !child.step(s) {
+                    *violated = true;
+                }
+                !*violated
+            }
+            Node::EventuallyWithin { child, bound, countdown, violated } => {
+                let holds = child.step(s);
+                if holds {
+                    *countdown = Some(*bound); // re-arm for the next obligation
+                } else if let Some(c) = countdown {
+                    if *c == 0 {
+                        *violated = true;
+                    } else {
+                        *countdown = Some(*c - 1);
+                    }
+                }
+                !*violated
+            }
+            Node::Until { lhs, rhs, violated, satisfied } => {
+                let r = rhs.step(s);
+                let l = lhs.step(s);
+                if r {
+                    *satisfied = true;
+                } else if !*satisfied && !l {
+                    *violated = true;
+                }
+                !*violated
+            }
+        }
+    }
+
+    /// Finalize the automaton at end of trace, discharging outstanding liveness
+    /// obligations that can no longer be met by any future tick. Returns whether the
+    /// subformula still holds once those obligations are resolved.
+    fn end_of_trace(&mut self) -> bool {
+        match self {
+            Node::Atom(_) => true,
+            Node::Not(c) => !c.end_of_trace(),
+            Node::And(a, b) => {
+                let l = a.end_of_trace();
+                let r = b.end_of_trace();
+                l && r
+            }
+            Node::Or(a, b) => {
+                let l = a.end_of_trace();
+                let r = b.end_of_trace();
+                l || r
+            }
+            Node::Next { child, .. } => {
+                // The obligation raised on the final tick can never be discharged
+                // online; treat it as vacuously met (the documented one-tick-late
+                // limitation of `next`).
+                child.end_of_trace();
+                true
+            }
+            Node::Always { child, violated } => {
+                if !child.end_of_trace() {
+                    *violated = true;
+                }
+                !*violated
+            }
+            Node::EventuallyWithin { child, violated, .. } => {
+                if !child.end_of_trace() {
+                    *violated = true;
+                }
+                !*violated
+            }
+            Node::Until { lhs, rhs, violated, satisfied } => {
+                lhs.end_of_trace();
+                rhs.end_of_trace();
+                if !*satisfied {
+                    // Strong until: ψ must eventually hold, and the trace ended
+                    // without it ever becoming true.
+                    *violated = true;
+                }
+                !*violated
+            }
+        }
+    }
+
+    /// Fold the automaton state into a replay hash.
+    fn hash_into(&self, h: &mut u64) {
+        fn mix(h: &mut u64, v: u64) {
+            *h = h.rotate_left(5) ^ v.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+        match self {
+            Node::Atom(_) => mix(h, 1),
+            Node::Not(c) => {
+                mix(h, 2);
+                c.hash_into(h);
+            }
+            Node::And(a, b) | Node::Or(a, b) => {
+                mix(h, 3);
+                a.hash_into(h);
+                b.hash_into(h);
+            }
+            Node::Next { child, prev } => {
+                mix(h, 4 ^ prev.map(|p| p as u64 + 1).unwrap_or(0) << 8);
+                child.hash_into(h);
+            }
+            Node::Always { child, violated } => {
+                mix(h, 5 ^ ((*violated as u64) << 8));
+                child.hash_into(h);
+            }
+            Node::EventuallyWithin { child, countdown, violated, .. } => {
+                mix(h, 6 ^ ((countdown.unwrap_or(u32::MAX) as u64) << 8) ^ ((*violated as u64) << 40));
+                child.hash_into(h);
+            }
+            Node::Until { lhs, rhs, violated, satisfied } => {
+                mix(h, 7 ^ ((*violated as u64) << 8) ^ ((*satisfied as u64) << 16));
+                lhs.hash_into(h);
+                rhs.hash_into(h);
+            }
+        }
+    }
+}
+
+/// A compiled monitor for one property, carrying the severity to report on
+/// violation.
+pub struct SafetyMonitor {
+    root: Node,
+    severity: Severity,
+    tripped: bool,
+}
+
+impl SafetyMonitor {
+    /// Compile `property` once into its monitor automaton.
+    pub fn compile(property: SafetyProperty, severity: Severity) -> Self {
+        Self { root: compile(property), severity, tripped: false }
+    }
+
+    /// Evaluate one tick. Returns `Some(violation)` the first tick the property is
+    /// violated; subsequent ticks return `None` (the latch stays tripped).
+    pub fn tick(&mut self, state: &RuntimeState) -> Option<SafetyViolation> {
+        let holds = self.root.step(state);
+        if !holds && !self.tripped {
+            self.tripped = true;
+            return Some(SafetyViolation { severity: self.severity });
+        }
+        None
+    }
+
+    /// Finalize at end of trace, flagging any outstanding strong-liveness obligation
+    /// (e.g. an `until` whose ψ never held) that no future tick can satisfy. Returns
+    /// `Some(violation)` if the property is first violated here.
+    pub fn finish(&mut self) -> Option<SafetyViolation> {
+        let holds = self.root.end_of_trace();
+        if !holds && !self.tripped {
+            self.tripped = true;
+            return Some(SafetyViolation { severity: self.severity });
+        }
+        None
+    }
+
+    /// Deterministic hash of the monitor state for replay verification.
+    pub fn replay_hash(&self) -> u64 {
+        let mut h: u64 = 0xCBF2_9CE4_8422_2325;
+        self.root.hash_into(&mut h);
+        h ^= self.tripped as u64;
+        h
+    }
+}
+
+/// Convenience: the common `always φ` guard over an atomic predicate.
+pub fn always<F>(pred: F) -> SafetyProperty
+where
+    F: Fn(&RuntimeState) -> bool + Send + Sync + 'static,
+{
+    SafetyProperty::Always(Box::new(SafetyProperty::Atom(Box::new(pred))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(rate: f32) -> RuntimeState {
+        RuntimeState { ts_us: 0, rate_bpm: rate, confidence: 1.0, rr_min: 4.0, rr_max: 12.0 }
+    }
+
+    #[test]
+    fn always_flags_on_first_violation() {
+        let prop = always(|s: &RuntimeState| s.rate_bpm >= s.rr_min && s.rate_bpm <= s.rr_max);
+        let mut mon = SafetyMonitor::compile(prop, Severity::Critical);
+        assert!(mon.tick(&state(6.0)).is_none());
+        let v = mon.tick(&state(20.0)).expect("out-of-band rate violates");
+        assert_eq!(v.severity, Severity::Critical);
+        // Latched: does not re-fire.
+        assert!(mon.tick(&state(20.0)).is_none());
+    }
+
+    #[test]
+    fn eventually_within_expires() {
+        let prop = SafetyProperty::EventuallyWithin(
+            2,
+            Box::new(SafetyProperty::Atom(Box::new(|s: &RuntimeState| s.confidence > 0.9))),
+        );
+        let mut mon = SafetyMonitor::compile(prop, Severity::Warning);
+        let bad = RuntimeState { confidence: 0.1, ..state(6.0) };
+        assert!(mon.tick(&bad).is_none());
+        assert!(mon.tick(&bad).is_none());
+        assert!(mon.tick(&bad).is_some());
+    }
+
+    #[test]
+    fn until_liveness_flagged_at_end_of_trace() {
+        // `confidence < 0.5` until `confidence >= 0.9`. φ holds every tick but ψ
+        // never does, so strong until is violated only once the trace ends.
+        let prop = SafetyProperty::Until(
+            Box::new(SafetyProperty::Atom(Box::new(|s: &RuntimeState| s.confidence < 0.5))),
+            Box::new(SafetyProperty::Atom(Box::new(|s: &RuntimeState| s.confidence >= 0.9))),
+        );
+        let mut mon = SafetyMonitor::compile(prop, Severity::Warning);
+        let waiting = RuntimeState { confidence: 0.1, ..state(6.0) };
+        assert!(mon.tick(&waiting).is_none());
+        assert!(mon.tick(&waiting).is_none());
+        // ψ never became true: the liveness obligation fails at end of trace.
+        assert!(mon.finish().is_some());
+    }
+}
@@ -0,0 +1,191 @@
+//! Epoch-based quorum voting across the safety swarm.
+//!
+//! Several [`AgentContainer`](crate::agent_container::AgentContainer)s may propose
+//! different target rates. Rather than let any single agent patch the controller,
+//! votes are reconciled within discrete decision *epochs*: each agent's confidence
+//! is a weighted vote for its proposed rate, and a patch is only emitted when a
+//! configurable quorum of total confidence weight agrees within
+//! `decision_epsilon_bpm`. Persistently-outlying agents lose weight over time, and
+//! each epoch's tally is returned as an [`EpochTally`]; the swarm does not store
+//! past tallies.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+pub struct QuorumConfig {
+    /// Fraction of total (reputation-weighted) confidence that must agree, e.g.
+    /// `0.66` for a supermajority.
+    pub quorum_fraction: f32,
+    /// Two votes agree when their target rates are within this band.
+    pub decision_epsilon_bpm: f32,
+    /// Epoch length in microseconds; votes are tallied per epoch.
+    pub epoch_us: i64,
+    /// Multiplicative reputation decay applied when an agent disagrees with the
+    /// winning cluster (in `(0, 1]`).
+    pub disagreement_decay: f32,
+    /// Reputation recovery applied when an agent agrees.
+    pub agreement_recovery: f32,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            quorum_fraction: 0.66,
+            decision_epsilon_bpm: 0.5,
+            epoch_us: 1_000_000,
+            disagreement_decay: 0.9,
+            agreement_recovery: 1.02,
+        }
+    }
+}
+
+/// A single agent's vote within an epoch.
+#[derive(Debug, Clone)]
+pub struct Vote {
+    /// Stable agent identity (its container `version`), also used for tie-breaking.
+    pub version: String,
+    /// Proposed target rate, breaths per minute.
+    pub target_bpm: f32,
+    /// Self-reported confidence in `[0, 1]`.
+    pub confidence: f32,
+}
+
+/// Per-epoch vote tally returned by [`SafetySwarm::tally`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochTally {
+    pub epoch: u64,
+    /// Winning target rate, if quorum was reached.
+    pub committed_bpm: Option<f32>,
+    /// Total reputation-weighted confidence that backed the winner.
+    pub winning_weight: f32,
+    /// Total reputation-weighted confidence cast this epoch.
+    pub total_weight: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Reputation {
+    version: String,
+    weight: f32,
+}
+
+/// Reconciles agent votes into committed patches via weighted quorum voting.
+#[derive(Debug, Clone)]
+pub struct SafetySwarm {
+    pub cfg: QuorumConfig,
+    reputations: Vec<Reputation>,
+    epoch: u64,
+}
+
+impl SafetySwarm {
+    pub fn new(cfg: QuorumConfig) -> Self {
+        Self { cfg, reputations: Vec::new(), epoch: 0 }
+    }
+
+    fn reputation(&mut self, version: &str) -> &mut f32 {
+        if let Some(idx) = self.reputations.iter().position(|r| r.version == version) {
+            &mut self.reputations[idx].weight
+        } else {
+            self.reputations.push(Reputation { version: String::from(version), weight: 1.0 });
+            let last = self.reputations.len() - 1;
+            &mut self.reputations[last].weight
+        }
+    }
+
+    /// Tally one epoch of votes. Returns the committed rate (if quorum was reached)
+    /// and the epoch's [`EpochTally`]. Agreement updates each agent's reputation so
+    /// a persistent outlier's weight decays.
+    pub fn tally(&mut self, votes: &[Vote]) -> (Option<f32>, EpochTally) {
+        let epoch = self.epoch;
+        self.epoch += 1;
+
+        // Reputation-weighted confidence per vote.
+        let weighted: Vec<(usize, f32)> = votes
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, v.confidence.clamp(0.0, 1.0) * *self.reputation(&v.version)))
+            .collect();
+        let total_weight: f32 = weighted.iter().map(|(_, w)| *w).sum();
+
+        // Find the cluster of agreeing votes with the greatest backing weight.
+        // Ties break on the lexicographically lowest version hash for determinism.
+        let mut best: Option<(f32, f32, &str)> = None; // (weight, target, version)
+        for (i, center_w) in &weighted {
+            let center = votes[*i].target_bpm;
+            let (cluster_w, weighted_target) = weighted.iter().fold((0.0f32, 0.0f32), |acc, (j, w)| {
+                if crate::math::abs_f32(votes[*j].target_bpm - center) <= self.cfg.decision_epsilon_bpm {
+                    (acc.0 + w, acc.1 + w * votes[*j].target_bpm)
+                } else {
+                    acc
+                }
+            });
+            let _ = center_w;
+            let version = votes[*i].version.as_str();
+            let replace = match best {
+                None => true,
+                Some((bw, _, bv)) => {
+                    cluster_w > bw || (cluster_w == bw && version < bv)
+                }
+            };
+            if replace {
+                let centroid = if cluster_w > 0.0 { weighted_target / cluster_w } else { center };
+                best = Some((cluster_w, centroid, version));
+            }
+        }
+
+        let (winning_weight, committed_bpm) = match best {
+            Some((w, target, _)) if total_weight > 0.0 && w >= self.cfg.quorum_fraction * total_weight => {
+                (w, Some(target))
+            }
+            Some((w, _, _)) => (w, None),
+            None => (0.0, None),
+        };
+
+        // Update reputations: agents inside the winning cluster recover, others decay.
+        if let Some(target) = committed_bpm {
+            let decay = self.cfg.disagreement_decay;
+            let recover = self.cfg.agreement_recovery;
+            let eps = self.cfg.decision_epsilon_bpm;
+            for v in votes {
+                let agrees = crate::math::abs_f32(v.target_bpm - target) <= eps;
+                let w = self.reputation(&v.version);
+                *w = (*w * if agrees { recover } else { decay }).clamp(0.05, 1.0);
+            }
+        }
+
+        let tally = EpochTally { epoch, committed_bpm, winning_weight, total_weight };
+        (committed_bpm, tally)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn vote(v: &str, bpm: f32, c: f32) -> Vote {
+        Vote { version: v.to_string(), target_bpm: bpm, confidence: c }
+    }
+
+    #[test]
+    fn supermajority_commits() {
+        let mut s = SafetySwarm::new(QuorumConfig::default());
+        let votes = vec![vote("a", 6.0, 0.9), vote("b", 6.1, 0.9), vote("c", 9.0, 0.5)];
+        let (committed, tally) = s.tally(&votes);
+        assert!(committed.is_some());
+        assert!((committed.unwrap() - 6.05).abs() < 0.2);
+        assert_eq!(tally.epoch, 0);
+    }
+
+    #[test]
+    fn outlier_weight_decays() {
+        let mut s = SafetySwarm::new(QuorumConfig::default());
+        let votes = vec![vote("a", 6.0, 0.9), vote("b", 6.0, 0.9), vote("c", 9.0, 0.9)];
+        for _ in 0..5 {
+            s.tally(&votes);
+        }
+        let w = *s.reputation("c");
+        assert!(w < 1.0);
+    }
+}
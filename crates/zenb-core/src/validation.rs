@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 /// Input validation layer for sensor data and control decisions.
 #[derive(Debug, Clone)]
 pub enum SensorError {